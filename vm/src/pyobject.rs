@@ -1,11 +1,14 @@
 use super::bytecode;
 use super::objint;
 use super::objtype;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{FromPrimitive, ToPrimitive, Zero};
 use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::fmt;
-use std::ops::{Add, Div, Mul, Sub};
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
 
 /* Python objects and references.
@@ -56,7 +59,29 @@ impl PyContext {
     }
 
     pub fn new_int(&self, i: i32) -> PyObjectRef {
-        PyObject::new(PyObjectKind::Integer { value: i }, self.type_type.clone())
+        self.new_bigint(BigInt::from(i))
+    }
+
+    pub fn new_bigint(&self, value: BigInt) -> PyObjectRef {
+        PyObject::new(PyObjectKind::Integer { value: value }, self.type_type.clone())
+    }
+
+    pub fn new_float(&self, value: f64) -> PyObjectRef {
+        PyObject::new(PyObjectKind::Float { value: value }, self.type_type.clone())
+    }
+
+    /// Parse a decimal or `0x`-prefixed hexadecimal integer literal. A literal
+    /// that does not parse raises a `ValueError` rather than panicking.
+    pub fn new_int_from_str(&self, s: &str) -> PyResult {
+        let parsed = if s.starts_with("0x") || s.starts_with("0X") {
+            BigInt::parse_bytes(s[2..].as_bytes(), 16)
+        } else {
+            s.parse::<BigInt>().ok()
+        };
+        match parsed {
+            Some(value) => Ok(self.new_bigint(value)),
+            None => Err(value_error(format!("invalid literal for int(): {}", s))),
+        }
     }
 
     pub fn new_str(&self, s: String) -> PyObjectRef {
@@ -67,6 +92,13 @@ impl PyContext {
         PyObject::new(PyObjectKind::Boolean { value: b }, self.type_type.clone())
     }
 
+    /// The `NotImplemented` singleton, returned by a binary dunder to signal that
+    /// it does not handle the given operand types so the reflected method should
+    /// be tried.
+    pub fn new_not_implemented(&self) -> PyObjectRef {
+        PyObject::new(PyObjectKind::NotImplemented, self.type_type.clone())
+    }
+
     pub fn new_class(&self, name: String) -> PyObjectRef {
         PyObject::new(PyObjectKind::Class { name: name }, self.type_type.clone())
     }
@@ -117,7 +149,10 @@ pub enum PyObjectKind {
         value: String,
     },
     Integer {
-        value: i32,
+        value: BigInt,
+    },
+    Float {
+        value: f64,
     },
     Boolean {
         value: bool,
@@ -129,7 +164,7 @@ pub enum PyObjectKind {
         elements: Vec<PyObjectRef>,
     },
     Dict {
-        elements: HashMap<String, PyObjectRef>,
+        elements: HashMap<PyObjectHashable, PyObjectRef>,
     },
     Iterator {
         position: usize,
@@ -154,6 +189,7 @@ pub enum PyObjectKind {
         name: String,
     },
     None,
+    NotImplemented,
     Class {
         name: String,
     },
@@ -171,6 +207,166 @@ impl fmt::Debug for PyObjectKind {
     }
 }
 
+/// A `PyObjectRef` wrapped so it can key a dict: `Hash` and `Eq` dispatch on
+/// the object's kind rather than on pointer identity. Integers and booleans
+/// hash their value, strings their bytes, and tuples the ordered combination of
+/// their elements' hashes. Unhashable kinds (lists, dicts) are rejected by
+/// `new` so they never reach the hash table.
+pub struct PyObjectHashable {
+    pub obj: PyObjectRef,
+}
+
+impl PyObjectHashable {
+    /// Wrap `obj` as a dict key, raising a `TypeError` for unhashable kinds.
+    pub fn new(rt: &mut Executor, obj: PyObjectRef) -> Result<PyObjectHashable, PyObjectRef> {
+        if is_hashable(&obj.borrow().kind) {
+            Ok(PyObjectHashable { obj: obj })
+        } else {
+            Err(type_error(rt, String::from("unhashable type")))
+        }
+    }
+}
+
+fn is_hashable(kind: &PyObjectKind) -> bool {
+    match kind {
+        PyObjectKind::Integer { .. }
+        | PyObjectKind::Float { .. }
+        | PyObjectKind::Boolean { .. }
+        | PyObjectKind::String { .. }
+        | PyObjectKind::None => true,
+        PyObjectKind::Tuple { ref elements } => {
+            elements.iter().all(|e| is_hashable(&e.borrow().kind))
+        }
+        _ => false,
+    }
+}
+
+fn hash_kind<H: Hasher>(kind: &PyObjectKind, state: &mut H) {
+    match kind {
+        PyObjectKind::Integer { ref value } => value.hash(state),
+        // `PartialEq` equates an integer with a numerically-equal float, so a
+        // float with no fractional part must hash like that integer (as CPython
+        // does) to keep the `Hash`/`Eq` contract; only genuinely fractional
+        // floats fall back to their bit pattern.
+        PyObjectKind::Float { ref value } => match BigInt::from_f64(*value) {
+            Some(ref int) if value.fract() == 0.0 => int.hash(state),
+            _ => value.to_bits().hash(state),
+        },
+        PyObjectKind::Boolean { ref value } => value.hash(state),
+        PyObjectKind::String { ref value } => value.as_bytes().hash(state),
+        PyObjectKind::Tuple { ref elements } => {
+            for element in elements {
+                hash_kind(&element.borrow().kind, state);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl Hash for PyObjectHashable {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_kind(&self.obj.borrow().kind, state);
+    }
+}
+
+impl PartialEq for PyObjectHashable {
+    fn eq(&self, other: &PyObjectHashable) -> bool {
+        *self.obj.borrow() == *other.obj.borrow()
+    }
+}
+
+impl Eq for PyObjectHashable {}
+
+/// Build a detached `RuntimeError` object. It needs no `Executor`, so `str()`,
+/// which has none, can raise it too; the message is wrapped in a string object
+/// the caller propagates.
+fn runtime_error(msg: String) -> PyObjectRef {
+    PyObject {
+        kind: PyObjectKind::String {
+            value: format!("RuntimeError: {}", msg),
+        },
+        typ: None,
+        dict: HashMap::new(),
+    }
+    .into_ref()
+}
+
+/// Build a detached `ValueError` object. Like `runtime_error`, it needs no
+/// `Executor`, so `PyContext` constructors can raise it too; the message rides
+/// in a string object the caller propagates through `PyResult`.
+fn value_error(msg: String) -> PyObjectRef {
+    PyObject {
+        kind: PyObjectKind::String {
+            value: format!("ValueError: {}", msg),
+        },
+        typ: None,
+        dict: HashMap::new(),
+    }
+    .into_ref()
+}
+
+/// Recognise a `StopIteration` exception, which ends a `__next__`-driven loop.
+fn is_stop_iteration(exc: &PyObjectRef) -> bool {
+    match exc.borrow().kind {
+        PyObjectKind::String { ref value } => value.starts_with("StopIteration"),
+        _ => false,
+    }
+}
+
+/// Render a float the way CPython's `repr` does: a fractional part is always
+/// present (`1.0`, never `1`), non-finite values use lower-case `nan`/`inf`,
+/// and magnitudes below roughly `1e-4` or from `1e16` up switch to exponential form
+/// with a signed, two-digit-minimum exponent (`1e+20`, `1e-05`).
+fn float_repr(value: f64) -> String {
+    if value.is_nan() {
+        return String::from("nan");
+    }
+    if value.is_infinite() {
+        return String::from(if value < 0.0 { "-inf" } else { "inf" });
+    }
+    if value == 0.0 {
+        return String::from(if value.is_sign_negative() { "-0.0" } else { "0.0" });
+    }
+    let exponent = value.abs().log10().floor() as i32;
+    if exponent < -4 || exponent >= 16 {
+        normalize_exponent(format!("{:e}", value))
+    } else {
+        let s = format!("{}", value);
+        if s.contains('.') {
+            s
+        } else {
+            format!("{}.0", s)
+        }
+    }
+}
+
+/// Rewrite Rust's `{:e}` output (`1e20`, `1.5e-5`) into Python's exponent style:
+/// an explicit sign and at least two digits (`1e+20`, `1.5e-05`).
+fn normalize_exponent(s: String) -> String {
+    match s.find('e') {
+        Some(idx) => {
+            let (mantissa, exp) = s.split_at(idx);
+            let exp = &exp[1..];
+            let (sign, digits) = match exp.strip_prefix('-') {
+                Some(rest) => ("-", rest),
+                None => ("+", exp),
+            };
+            format!("{}e{}{:0>2}", mantissa, sign, digits)
+        }
+        None => s,
+    }
+}
+
+/// `str()` a child object without panicking. A child that is already borrowed
+/// is a recursive container reached through itself; like CPython we render it
+/// as `[...]` rather than crashing on the re-entrant borrow.
+fn str_child(obj: &PyObjectRef) -> Result<String, PyObjectRef> {
+    match obj.try_borrow_mut() {
+        Ok(inner) => inner.str(),
+        Err(_) => Ok(String::from("[...]")),
+    }
+}
+
 impl PyObject {
     pub fn new(kind: PyObjectKind, typ: PyObjectRef) -> PyObjectRef {
         PyObject {
@@ -190,79 +386,149 @@ impl PyObject {
         }
     }
 
-    pub fn str(&self) -> String {
+    pub fn str(&self) -> Result<String, PyObjectRef> {
         match self.kind {
-            PyObjectKind::String { ref value } => value.clone(),
-            PyObjectKind::Integer { ref value } => format!("{:?}", value),
-            PyObjectKind::Boolean { ref value } => format!("{:?}", value),
-            PyObjectKind::List { ref elements } => format!(
-                "[{}]",
-                elements
-                    .iter()
-                    .map(|elem| elem.borrow_mut().str())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            ),
-            PyObjectKind::Tuple { ref elements } => format!(
-                "{{{}}}",
-                elements
-                    .iter()
-                    .map(|elem| elem.borrow_mut().str())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            ),
-            PyObjectKind::None => String::from("None"),
-            PyObjectKind::Class { ref name } => format!("<class '{}'>", name),
-            PyObjectKind::Code { code: _ } => format!("<code>"),
-            PyObjectKind::Function { code: _ } => format!("<func>"),
-            PyObjectKind::RustFunction { function: _ } => format!("<rustfunc>"),
-            PyObjectKind::Module { ref name } => format!("<module '{}'>", name),
+            PyObjectKind::String { ref value } => Ok(value.clone()),
+            PyObjectKind::Integer { ref value } => Ok(format!("{}", value)),
+            PyObjectKind::Float { ref value } => Ok(float_repr(*value)),
+            PyObjectKind::Boolean { ref value } => Ok(format!("{:?}", value)),
+            PyObjectKind::List { ref elements } => {
+                let mut parts = Vec::new();
+                for element in elements {
+                    parts.push(str_child(element)?);
+                }
+                Ok(format!("[{}]", parts.join(", ")))
+            }
+            PyObjectKind::Tuple { ref elements } => {
+                let mut parts = Vec::new();
+                for element in elements {
+                    parts.push(str_child(element)?);
+                }
+                Ok(format!("{{{}}}", parts.join(", ")))
+            }
+            PyObjectKind::None => Ok(String::from("None")),
+            PyObjectKind::NotImplemented => Ok(String::from("NotImplemented")),
+            PyObjectKind::Class { ref name } => Ok(format!("<class '{}'>", name)),
+            PyObjectKind::Code { code: _ } => Ok(format!("<code>")),
+            PyObjectKind::Function { code: _ } => Ok(format!("<func>")),
+            PyObjectKind::RustFunction { function: _ } => Ok(format!("<rustfunc>")),
+            PyObjectKind::Module { ref name } => Ok(format!("<module '{}'>", name)),
             PyObjectKind::Slice {
                 ref start,
                 ref stop,
                 ref step,
-            } => format!("<slice '{:?}:{:?}:{:?}'>", start, stop, step),
+            } => Ok(format!("<slice '{:?}:{:?}:{:?}'>", start, stop, step)),
             PyObjectKind::Iterator {
                 ref position,
                 ref iterated_obj,
-            } => format!(
+            } => Ok(format!(
                 "<iter pos {} in {}>",
                 position,
-                iterated_obj.borrow_mut().str()
-            ),
-            _ => {
-                println!("Not impl {:?}", self);
-                panic!("Not impl");
-            }
+                str_child(iterated_obj)?
+            )),
+            _ => Err(runtime_error(format!("cannot str {:?}", self))),
         }
     }
 
-    // Implement iterator protocol:
-    pub fn nxt(&mut self) -> Option<PyObjectRef> {
+    // Implement iterator protocol. `Ok(None)` signals exhaustion; a borrow
+    // conflict (the container is already borrowed when the iterator advances)
+    // surfaces as a returned `RuntimeError` rather than a panic. Built-in iterables advance a
+    // position cursor over the wrapped object; any other object is driven
+    // through its `__next__` dunder, with a `StopIteration` treated as the end.
+    pub fn nxt(&mut self, rt: &mut Executor) -> Result<Option<PyObjectRef>, PyObjectRef> {
         match self.kind {
             PyObjectKind::Iterator {
                 ref mut position,
                 iterated_obj: ref iterated_obj_ref,
             } => {
-                let iterated_obj = &*iterated_obj_ref.borrow_mut();
-                match iterated_obj.kind {
-                    PyObjectKind::List { ref elements } => {
-                        if *position < elements.len() {
-                            let obj_ref = elements[*position].clone();
+                {
+                    let guard = match iterated_obj_ref.try_borrow_mut() {
+                        Ok(guard) => guard,
+                        Err(_) => {
+                            return Err(runtime_error(String::from(
+                                "container is already borrowed during iteration",
+                            )))
+                        }
+                    };
+                    match guard.kind {
+                        PyObjectKind::List { ref elements }
+                        | PyObjectKind::Tuple { ref elements } => {
+                            if *position < elements.len() {
+                                let obj_ref = elements[*position].clone();
+                                *position += 1;
+                                return Ok(Some(obj_ref));
+                            } else {
+                                return Ok(None);
+                            }
+                        }
+                        PyObjectKind::String { ref value } => {
+                            return match value.chars().nth(*position) {
+                                Some(c) => {
+                                    *position += 1;
+                                    Ok(Some(rt.new_str(c.to_string())))
+                                }
+                                None => Ok(None),
+                            };
+                        }
+                        PyObjectKind::Dict { ref elements } => {
+                            let keys: Vec<PyObjectRef> =
+                                elements.keys().map(|key| key.obj.clone()).collect();
+                            if *position < keys.len() {
+                                let key = keys[*position].clone();
+                                *position += 1;
+                                return Ok(Some(key));
+                            } else {
+                                return Ok(None);
+                            }
+                        }
+                        PyObjectKind::Slice { start, stop, step } => {
+                            let start = start.unwrap_or(0);
+                            let step = step.unwrap_or(1);
+                            if step == 0 {
+                                return Err(value_error(String::from("slice step cannot be zero")));
+                            }
+                            let current = start + (*position as i32) * step;
+                            let exhausted = match stop {
+                                Some(stop) if step >= 0 => current >= stop,
+                                Some(stop) => current <= stop,
+                                None => false,
+                            };
+                            if exhausted {
+                                return Ok(None);
+                            }
                             *position += 1;
-                            Some(obj_ref)
-                        } else {
-                            None
+                            return Ok(Some(rt.context().new_int(current)));
                         }
-                    }
-                    _ => {
-                        panic!("NOT IMPL");
+                        _ => {}
                     }
                 }
+                // Fallback: drive an arbitrary object through the iterator
+                // protocol, mirroring `iter(obj)` then `next(it)`. An object
+                // that is already an iterator (has `__next__`) is advanced
+                // directly; an iterable is first resolved to its iterator via
+                // `__iter__`.
+                let iterator = if get_special_method(iterated_obj_ref, "__next__").is_some() {
+                    iterated_obj_ref.clone()
+                } else if let Some(iter_method) = get_special_method(iterated_obj_ref, "__iter__") {
+                    iter_method.borrow().call(rt, vec![iterated_obj_ref.clone()])?
+                } else {
+                    return Err(type_error(rt, String::from("object is not iterable")));
+                };
+                match get_special_method(&iterator, "__next__") {
+                    Some(method) => match method.borrow().call(rt, vec![iterator.clone()]) {
+                        Ok(value) => Ok(Some(value)),
+                        Err(exc) => {
+                            if is_stop_iteration(&exc) {
+                                Ok(None)
+                            } else {
+                                Err(exc)
+                            }
+                        }
+                    },
+                    None => Err(type_error(rt, String::from("object is not an iterator"))),
+                }
             }
-            _ => {
-                panic!("NOT IMPL");
-            }
+            _ => Err(type_error(rt, String::from("object is not an iterator"))),
         }
     }
 
@@ -272,142 +538,258 @@ impl PyObject {
     }
 }
 
-impl<'a> Add<&'a PyObject> for &'a PyObject {
-    type Output = PyObjectKind;
+// Binary operators dispatch on the built-in fast paths first and then fall
+// back to the corresponding dunder method looked up on the operands' type
+// objects, mirroring CPython's number protocol.  Because a user-defined
+// `__add__` calls back into the interpreter, these take an `Executor` and
+// return a `PyResult` whose `Err` carries a raised exception object.
+
+/// Build a Python-level `TypeError` object carrying `msg`.  There is not yet a
+/// proper exception hierarchy, so the message is wrapped in a string object the
+/// caller propagates through `PyResult`.
+fn type_error(rt: &mut Executor, msg: String) -> PyObjectRef {
+    rt.new_str(format!("TypeError: {}", msg))
+}
 
-    fn add(self, rhs: &'a PyObject) -> Self::Output {
-        match self.kind {
-            PyObjectKind::Integer { value: ref value1 } => match &rhs.kind {
-                PyObjectKind::Integer { value: ref value2 } => PyObjectKind::Integer {
-                    value: value1 + value2,
-                },
-                _ => {
-                    panic!("NOT IMPL");
-                }
-            },
-            PyObjectKind::String { value: ref value1 } => match rhs.kind {
-                PyObjectKind::String { value: ref value2 } => PyObjectKind::String {
-                    value: format!("{}{}", value1, value2),
-                },
-                _ => {
-                    panic!("NOT IMPL");
-                }
-            },
-            PyObjectKind::List { elements: ref e1 } => match rhs.kind {
-                PyObjectKind::List { elements: ref e2 } => PyObjectKind::List {
-                    elements: e1.iter().chain(e2.iter()).map(|e| e.clone()).collect(),
-                },
-                _ => {
-                    panic!("NOT IMPL");
-                }
-            },
-            _ => {
-                // TODO: Lookup __add__ method in dictionary?
-                panic!("NOT IMPL");
-            }
+/// Build a Python-level `ZeroDivisionError` object carrying `msg`.
+fn zero_division_error(rt: &mut Executor, msg: String) -> PyObjectRef {
+    rt.new_str(format!("ZeroDivisionError: {}", msg))
+}
+
+/// Recognise the `NotImplemented` sentinel a dunder returns when it does not
+/// handle the given operands.
+fn is_not_implemented(obj: &PyObjectRef) -> bool {
+    match obj.borrow().kind {
+        PyObjectKind::NotImplemented => true,
+        _ => false,
+    }
+}
+
+/// Fetch a special (dunder) method from an object's type object dict.
+fn get_special_method(obj: &PyObjectRef, name: &str) -> Option<PyObjectRef> {
+    match obj.borrow().typ {
+        Some(ref typ) => typ.borrow().dict.get(name).cloned(),
+        None => None,
+    }
+}
+
+/// Dispatch a binary operator to `op` on the left operand, falling back to the
+/// reflected `rop` on the right operand, and finally to a `TypeError`.
+fn binary_dunder(
+    rt: &mut Executor,
+    a: &PyObjectRef,
+    b: &PyObjectRef,
+    op: &str,
+    rop: &str,
+) -> PyResult {
+    if let Some(method) = get_special_method(a, op) {
+        let result = method.borrow().call(rt, vec![a.clone(), b.clone()])?;
+        if !is_not_implemented(&result) {
+            return Ok(result);
         }
     }
+    if let Some(method) = get_special_method(b, rop) {
+        let result = method.borrow().call(rt, vec![b.clone(), a.clone()])?;
+        if !is_not_implemented(&result) {
+            return Ok(result);
+        }
+    }
+    Err(type_error(
+        rt,
+        format!("unsupported operand type(s) for {}", op),
+    ))
 }
 
-impl<'a> Sub<&'a PyObject> for &'a PyObject {
-    type Output = PyObjectKind;
+fn simple_value(rt: &mut Executor, kind: PyObjectKind) -> PyObjectRef {
+    PyObject::new(kind, rt.get_type())
+}
 
-    fn sub(self, rhs: &'a PyObject) -> Self::Output {
-        match self.kind {
-            PyObjectKind::Integer { value: value1 } => match rhs.kind {
-                PyObjectKind::Integer { value: value2 } => PyObjectKind::Integer {
-                    value: value1 - value2,
-                },
-                _ => {
-                    panic!("NOT IMPL");
-                }
-            },
-            _ => {
-                panic!("NOT IMPL");
-            }
+pub fn add(rt: &mut Executor, a: &PyObjectRef, b: &PyObjectRef) -> PyResult {
+    let fast = match (&a.borrow().kind, &b.borrow().kind) {
+        (PyObjectKind::Integer { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+            Some(PyObjectKind::Integer { value: v1 + v2 })
         }
+        (PyObjectKind::Float { value: v1 }, PyObjectKind::Float { value: v2 }) => {
+            Some(PyObjectKind::Float { value: v1 + v2 })
+        }
+        (PyObjectKind::Integer { value: v1 }, PyObjectKind::Float { value: v2 }) => {
+            Some(PyObjectKind::Float {
+                value: v1.to_f64().unwrap() + v2,
+            })
+        }
+        (PyObjectKind::Float { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+            Some(PyObjectKind::Float {
+                value: v1 + v2.to_f64().unwrap(),
+            })
+        }
+        (PyObjectKind::String { value: v1 }, PyObjectKind::String { value: v2 }) => {
+            Some(PyObjectKind::String {
+                value: format!("{}{}", v1, v2),
+            })
+        }
+        (PyObjectKind::List { elements: e1 }, PyObjectKind::List { elements: e2 }) => {
+            Some(PyObjectKind::List {
+                elements: e1.iter().chain(e2.iter()).cloned().collect(),
+            })
+        }
+        _ => None,
+    };
+    match fast {
+        Some(kind) => Ok(simple_value(rt, kind)),
+        None => binary_dunder(rt, a, b, "__add__", "__radd__"),
     }
 }
 
-impl<'a> Mul<&'a PyObject> for &'a PyObject {
-    type Output = PyObjectKind;
+pub fn sub(rt: &mut Executor, a: &PyObjectRef, b: &PyObjectRef) -> PyResult {
+    let fast = match (&a.borrow().kind, &b.borrow().kind) {
+        (PyObjectKind::Integer { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+            Some(PyObjectKind::Integer { value: v1 - v2 })
+        }
+        (PyObjectKind::Float { value: v1 }, PyObjectKind::Float { value: v2 }) => {
+            Some(PyObjectKind::Float { value: v1 - v2 })
+        }
+        (PyObjectKind::Integer { value: v1 }, PyObjectKind::Float { value: v2 }) => {
+            Some(PyObjectKind::Float {
+                value: v1.to_f64().unwrap() - v2,
+            })
+        }
+        (PyObjectKind::Float { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+            Some(PyObjectKind::Float {
+                value: v1 - v2.to_f64().unwrap(),
+            })
+        }
+        _ => None,
+    };
+    match fast {
+        Some(kind) => Ok(simple_value(rt, kind)),
+        None => binary_dunder(rt, a, b, "__sub__", "__rsub__"),
+    }
+}
 
-    fn mul(self, rhs: &'a PyObject) -> Self::Output {
-        match self.kind {
-            PyObjectKind::Integer { value: value1 } => match rhs.kind {
-                PyObjectKind::Integer { value: value2 } => PyObjectKind::Integer {
-                    value: value1 * value2,
-                },
-                _ => {
-                    panic!("NOT IMPL");
-                }
-            },
-            PyObjectKind::String { value: ref value1 } => match rhs.kind {
-                PyObjectKind::Integer { value: value2 } => {
-                    let mut result = String::new();
-                    for _x in 0..value2 {
-                        result.push_str(value1.as_str());
-                    }
-                    PyObjectKind::String { value: result }
-                }
-                _ => {
-                    panic!("NOT IMPL");
-                }
-            },
-            _ => {
-                panic!("NOT IMPL");
+pub fn mul(rt: &mut Executor, a: &PyObjectRef, b: &PyObjectRef) -> PyResult {
+    let fast = match (&a.borrow().kind, &b.borrow().kind) {
+        (PyObjectKind::Integer { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+            Some(PyObjectKind::Integer { value: v1 * v2 })
+        }
+        (PyObjectKind::Float { value: v1 }, PyObjectKind::Float { value: v2 }) => {
+            Some(PyObjectKind::Float { value: v1 * v2 })
+        }
+        (PyObjectKind::Integer { value: v1 }, PyObjectKind::Float { value: v2 }) => {
+            Some(PyObjectKind::Float {
+                value: v1.to_f64().unwrap() * v2,
+            })
+        }
+        (PyObjectKind::Float { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+            Some(PyObjectKind::Float {
+                value: v1 * v2.to_f64().unwrap(),
+            })
+        }
+        (PyObjectKind::String { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+            let mut result = String::new();
+            for _x in 0..v2.to_usize().unwrap_or(0) {
+                result.push_str(v1.as_str());
             }
+            Some(PyObjectKind::String { value: result })
         }
+        _ => None,
+    };
+    match fast {
+        Some(kind) => Ok(simple_value(rt, kind)),
+        None => binary_dunder(rt, a, b, "__mul__", "__rmul__"),
     }
 }
 
-impl<'a> Div<&'a PyObject> for &'a PyObject {
-    type Output = PyObjectKind;
-
-    fn div(self, rhs: &'a PyObject) -> Self::Output {
-        match (&self.kind, &rhs.kind) {
-            (PyObjectKind::Integer { value: value1 }, PyObjectKind::Integer { value: value2 }) => {
-                PyObjectKind::Integer {
-                    value: value1 / value2,
-                }
+pub fn div(rt: &mut Executor, a: &PyObjectRef, b: &PyObjectRef) -> PyResult {
+    // True division always produces a float, so `int / int` is promoted. A zero
+    // divisor raises `ZeroDivisionError` rather than producing `inf`.
+    let fast = match (&a.borrow().kind, &b.borrow().kind) {
+        (PyObjectKind::Integer { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+            if v2.is_zero() {
+                return Err(zero_division_error(rt, String::from("division by zero")));
             }
-            _ => {
-                panic!("NOT IMPL");
+            Some(PyObjectKind::Float {
+                value: v1.to_f64().unwrap() / v2.to_f64().unwrap(),
+            })
+        }
+        (PyObjectKind::Float { value: v1 }, PyObjectKind::Float { value: v2 }) => {
+            if *v2 == 0.0 {
+                return Err(zero_division_error(rt, String::from("float division by zero")));
+            }
+            Some(PyObjectKind::Float { value: v1 / v2 })
+        }
+        (PyObjectKind::Integer { value: v1 }, PyObjectKind::Float { value: v2 }) => {
+            if *v2 == 0.0 {
+                return Err(zero_division_error(rt, String::from("float division by zero")));
             }
+            Some(PyObjectKind::Float {
+                value: v1.to_f64().unwrap() / v2,
+            })
         }
+        (PyObjectKind::Float { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+            if v2.is_zero() {
+                return Err(zero_division_error(rt, String::from("float division by zero")));
+            }
+            Some(PyObjectKind::Float {
+                value: v1 / v2.to_f64().unwrap(),
+            })
+        }
+        _ => None,
+    };
+    match fast {
+        Some(kind) => Ok(simple_value(rt, kind)),
+        None => binary_dunder(rt, a, b, "__truediv__", "__rtruediv__"),
+    }
+}
+
+pub fn floordiv(rt: &mut Executor, a: &PyObjectRef, b: &PyObjectRef) -> PyResult {
+    // Floor division keeps integers integral, flooring toward negative infinity.
+    // A zero divisor raises `ZeroDivisionError` instead of panicking.
+    let fast = match (&a.borrow().kind, &b.borrow().kind) {
+        (PyObjectKind::Integer { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+            if v2.is_zero() {
+                return Err(zero_division_error(
+                    rt,
+                    String::from("integer division or modulo by zero"),
+                ));
+            }
+            Some(PyObjectKind::Integer {
+                value: v1.div_floor(v2),
+            })
+        }
+        _ => None,
+    };
+    match fast {
+        Some(kind) => Ok(simple_value(rt, kind)),
+        None => binary_dunder(rt, a, b, "__floordiv__", "__rfloordiv__"),
     }
 }
 
-// impl<'a> PartialEq<&'a PyObject> for &'a PyObject {
+// Structural equality and ordering used by internal Rust machinery (list
+// comparison, container lookups).  Unknown combinations compare unequal rather
+// than panicking; rich Python-level comparison with dunder dispatch is done
+// through `richcompare`.
 impl PartialEq for PyObject {
     fn eq(&self, other: &PyObject) -> bool {
         match (&self.kind, &other.kind) {
             (
-                PyObjectKind::Integer { value: ref v1i },
-                PyObjectKind::Integer { value: ref v2i },
-            ) => v2i == v1i,
-            (PyObjectKind::String { value: ref v1i }, PyObjectKind::String { value: ref v2i }) => {
-                *v2i == *v1i
+                PyObjectKind::Integer { value: v1 },
+                PyObjectKind::Integer { value: v2 },
+            ) => v1 == v2,
+            (PyObjectKind::Float { value: v1 }, PyObjectKind::Float { value: v2 }) => v1 == v2,
+            (PyObjectKind::Integer { value: v1 }, PyObjectKind::Float { value: v2 }) => {
+                v1.to_f64().map_or(false, |v1| v1 == *v2)
             }
-            /*
-            (&NativeType::Float(ref v1f), &NativeType::Float(ref v2f)) => {
-                curr_frame.stack.push(Rc::new(NativeType::Boolean(v2f == v1f)));
-            },
-            */
-            (PyObjectKind::String { value: ref v1s }, &PyObjectKind::String { value: ref v2s }) => {
-                v2s == v1s
+            (PyObjectKind::Float { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+                v2.to_f64().map_or(false, |v2| *v1 == v2)
             }
-            (PyObjectKind::List { elements: ref l1 }, PyObjectKind::List { elements: ref l2 }) => {
-                if l1.len() == l2.len() {
-                    Iterator::zip(l1.iter(), l2.iter()).all(|elem| elem.0 == elem.1)
-                } else {
-                    false
-                }
+            (PyObjectKind::String { value: v1 }, PyObjectKind::String { value: v2 }) => v1 == v2,
+            (PyObjectKind::Boolean { value: v1 }, PyObjectKind::Boolean { value: v2 }) => v1 == v2,
+            (PyObjectKind::List { elements: l1 }, PyObjectKind::List { elements: l2 })
+            | (PyObjectKind::Tuple { elements: l1 }, PyObjectKind::Tuple { elements: l2 }) => {
+                l1.len() == l2.len() && Iterator::zip(l1.iter(), l2.iter()).all(|e| e.0 == e.1)
             }
-            _ => panic!(
-                "TypeError in COMPARE_OP: can't compare {:?} with {:?}",
-                self, other
-            ),
+            (PyObjectKind::None, PyObjectKind::None) => true,
+            _ => false,
         }
     }
 }
@@ -416,46 +798,146 @@ impl Eq for PyObject {}
 
 impl PartialOrd for PyObject {
     fn partial_cmp(&self, other: &PyObject) -> Option<Ordering> {
-        Some(self.cmp(other))
+        match (&self.kind, &other.kind) {
+            (PyObjectKind::Integer { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+                Some(v1.cmp(v2))
+            }
+            (PyObjectKind::Float { value: v1 }, PyObjectKind::Float { value: v2 }) => {
+                v1.partial_cmp(v2)
+            }
+            (PyObjectKind::Integer { value: v1 }, PyObjectKind::Float { value: v2 }) => {
+                v1.to_f64().and_then(|v1| v1.partial_cmp(v2))
+            }
+            (PyObjectKind::Float { value: v1 }, PyObjectKind::Integer { value: v2 }) => {
+                v2.to_f64().and_then(|v2| v1.partial_cmp(&v2))
+            }
+            (PyObjectKind::String { value: v1 }, PyObjectKind::String { value: v2 }) => {
+                Some(v1.cmp(v2))
+            }
+            (PyObjectKind::Boolean { value: v1 }, PyObjectKind::Boolean { value: v2 }) => {
+                Some(v1.cmp(v2))
+            }
+            (PyObjectKind::List { elements: l1 }, PyObjectKind::List { elements: l2 })
+            | (PyObjectKind::Tuple { elements: l1 }, PyObjectKind::Tuple { elements: l2 }) => {
+                cmp_elements(l1, l2)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Lexicographically compare two element sequences, stopping at the first pair
+/// that orders unequal. Returns `None` if any pair is itself incomparable.
+fn cmp_elements(l1: &[PyObjectRef], l2: &[PyObjectRef]) -> Option<Ordering> {
+    for (a, b) in Iterator::zip(l1.iter(), l2.iter()) {
+        match a.borrow().partial_cmp(&b.borrow()) {
+            Some(Ordering::Equal) => continue,
+            other => return other,
+        }
     }
+    Some(l1.len().cmp(&l2.len()))
 }
 
+// A total order is still required by `Ord`-bound internal collections. Within a
+// comparable pair it defers to `partial_cmp`; genuinely incomparable kinds
+// (e.g. `5` vs `"a"`) are ordered by a stable per-kind rank instead of
+// collapsing to `Equal`, which would corrupt sorts and break transitivity.
 impl Ord for PyObject {
     fn cmp(&self, other: &PyObject) -> Ordering {
-        match (&self.kind, &other.kind) {
-            (PyObjectKind::Integer { value: v1 }, PyObjectKind::Integer { value: ref v2 }) => {
-                v1.cmp(v2)
-            }
-            _ => panic!("Not impl"),
+        match self.partial_cmp(other) {
+            Some(ordering) => ordering,
+            None => kind_rank(&self.kind).cmp(&kind_rank(&other.kind)),
         }
     }
 }
 
+/// A stable ordinal per `PyObjectKind`, used only to give `Ord` a total
+/// fallback for operands that have no meaningful value comparison.
+fn kind_rank(kind: &PyObjectKind) -> u8 {
+    match kind {
+        PyObjectKind::None => 0,
+        PyObjectKind::Boolean { .. } => 1,
+        PyObjectKind::Integer { .. } => 2,
+        PyObjectKind::Float { .. } => 3,
+        PyObjectKind::String { .. } => 4,
+        PyObjectKind::Tuple { .. } => 5,
+        PyObjectKind::List { .. } => 6,
+        _ => 7,
+    }
+}
+
+/// Dispatch a rich comparison to the `op` dunder (`__eq__`, `__lt__`, ...),
+/// falling back to the reflected method before giving up with a `TypeError`.
+pub fn richcompare(
+    rt: &mut Executor,
+    a: &PyObjectRef,
+    b: &PyObjectRef,
+    op: &str,
+    rop: &str,
+) -> PyResult {
+    binary_dunder(rt, a, b, op, rop)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{PyContext, PyObjectKind};
+    use super::{BigInt, Executor, PyContext, PyObjectKind, PyObjectRef, PyResult};
+
+    // A minimal executor used to exercise the operator fast paths without a
+    // full virtual machine.
+    struct MockExecutor {
+        ctx: PyContext,
+    }
+
+    impl MockExecutor {
+        fn new() -> MockExecutor {
+            MockExecutor {
+                ctx: PyContext::new(),
+            }
+        }
+    }
+
+    impl Executor for MockExecutor {
+        fn call(&mut self, _func: PyObjectRef) -> PyResult {
+            panic!("MockExecutor cannot call");
+        }
+        fn new_str(&self, s: String) -> PyObjectRef {
+            self.ctx.new_str(s)
+        }
+        fn new_bool(&self, b: bool) -> PyObjectRef {
+            self.ctx.new_bool(b)
+        }
+        fn get_none(&self) -> PyObjectRef {
+            self.ctx.new_bool(false)
+        }
+        fn get_type(&self) -> PyObjectRef {
+            self.ctx.type_type.clone()
+        }
+        fn context(&self) -> &PyContext {
+            &self.ctx
+        }
+    }
 
     #[test]
     fn test_add_py_integers() {
-        let ctx = PyContext::new();
-        let a = ctx.new_int(33);
-        let b = ctx.new_int(12);
-        let c = &*a.borrow() + &*b.borrow();
-        match c {
-            PyObjectKind::Integer { value } => assert_eq!(value, 45),
+        let mut rt = MockExecutor::new();
+        let a = rt.ctx.new_int(33);
+        let b = rt.ctx.new_int(12);
+        let c = super::add(&mut rt, &a, &b).unwrap();
+        match c.borrow().kind {
+            PyObjectKind::Integer { ref value } => assert_eq!(*value, BigInt::from(45)),
             _ => assert!(false),
         }
     }
 
     #[test]
     fn test_multiply_str() {
-        let ctx = PyContext::new();
-        let a = ctx.new_str(String::from("Hello "));
-        let b = ctx.new_int(4);
-        let c = &*a.borrow() * &*b.borrow();
-        match c {
-            PyObjectKind::String { value } => {
-                assert_eq!(value, String::from("Hello Hello Hello Hello "))
+        let mut rt = MockExecutor::new();
+        let a = rt.ctx.new_str(String::from("Hello "));
+        let b = rt.ctx.new_int(4);
+        let c = super::mul(&mut rt, &a, &b).unwrap();
+        match c.borrow().kind {
+            PyObjectKind::String { ref value } => {
+                assert_eq!(*value, String::from("Hello Hello Hello Hello "))
             }
             _ => assert!(false),
         }
@@ -463,6 +945,70 @@ mod tests {
 
     #[test]
     fn test_type_type() {
-        let ctx = PyContext::new();
+        let _ctx = PyContext::new();
+    }
+
+    #[test]
+    fn test_dict_int_keys() {
+        use std::collections::HashMap;
+        let mut rt = MockExecutor::new();
+        let seven = rt.ctx.new_int(7);
+        let also_seven = rt.ctx.new_int(7);
+        let key = super::PyObjectHashable::new(&mut rt, seven).unwrap();
+        let same = super::PyObjectHashable::new(&mut rt, also_seven).unwrap();
+        let mut map = HashMap::new();
+        map.insert(key, rt.ctx.new_str(String::from("seven")));
+        assert!(map.contains_key(&same));
+    }
+
+    #[test]
+    fn test_dict_tuple_keys() {
+        use std::collections::HashMap;
+        let mut rt = MockExecutor::new();
+        let make = |rt: &mut MockExecutor| {
+            super::PyObject::new(
+                PyObjectKind::Tuple {
+                    elements: vec![rt.ctx.new_int(1), rt.ctx.new_int(2)],
+                },
+                rt.get_type(),
+            )
+        };
+        let key_tuple = make(&mut rt);
+        let same_tuple = make(&mut rt);
+        let key = super::PyObjectHashable::new(&mut rt, key_tuple).unwrap();
+        let same = super::PyObjectHashable::new(&mut rt, same_tuple).unwrap();
+        let mut map = HashMap::new();
+        map.insert(key, rt.ctx.new_str(String::from("onetwo")));
+        assert!(map.contains_key(&same));
+    }
+
+    #[test]
+    fn test_iterate_string() {
+        let mut rt = MockExecutor::new();
+        let s = rt.ctx.new_str(String::from("ab"));
+        let it = super::PyObject::new(
+            PyObjectKind::Iterator {
+                position: 0,
+                iterated_obj: s,
+            },
+            rt.get_type(),
+        );
+        let first = it.borrow_mut().nxt(&mut rt).unwrap().unwrap();
+        assert_eq!(first.borrow().str().unwrap(), "a");
+        let second = it.borrow_mut().nxt(&mut rt).unwrap().unwrap();
+        assert_eq!(second.borrow().str().unwrap(), "b");
+        assert!(it.borrow_mut().nxt(&mut rt).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_unhashable_list_rejected() {
+        let mut rt = MockExecutor::new();
+        let list = super::PyObject::new(
+            PyObjectKind::List {
+                elements: Vec::new(),
+            },
+            rt.get_type(),
+        );
+        assert!(super::PyObjectHashable::new(&mut rt, list).is_err());
     }
 }
\ No newline at end of file